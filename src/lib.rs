@@ -29,16 +29,178 @@
 //! assert_eq!(vec, &[1, 2, 3, 4, 4, 1, 2, 3, 4]);
 //! ```
 
+use std::collections::TryReserveError;
 use std::convert::AsMut;
 use std::convert::AsRef;
+use std::io;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::ops::Range;
 use std::slice;
 
-/// Allows pushing to a Vec while keeping a reference to it's content.
-pub trait AsFixedCapacityVec {
+#[cfg(feature = "allocator-api2")]
+extern crate allocator_api2;
+
+#[cfg(feature = "allocator-api2")]
+use allocator_api2::alloc::Allocator;
+#[cfg(feature = "allocator-api2")]
+use allocator_api2::vec::Vec as AllocVec;
+
+/// The growable buffer a [`FixedCapacityVec`] splits into a read view and a write view.
+///
+/// Implemented for `std::vec::Vec<T>` unconditionally, and for `allocator_api2::vec::Vec<T, A>`
+/// behind the `allocator-api2` feature. Third-party Vec-like buffers can implement this trait
+/// too.
+pub trait FixedCapacityBuffer {
+    /// The element type stored in this buffer.
     type Item;
+    /// The error produced when `try_reserve` fails to grow the buffer.
+    type Error;
+
+    fn fc_len(&self) -> usize;
+    fn fc_capacity(&self) -> usize;
+    fn fc_as_slice(&self) -> &[Self::Item];
+    fn fc_as_mut_slice(&mut self) -> &mut [Self::Item];
+    fn fc_as_mut_ptr(&mut self) -> *mut Self::Item;
+    fn fc_reserve(&mut self, additional: usize);
+    fn fc_try_reserve(&mut self, additional: usize) -> Result<(), Self::Error>;
+    fn fc_push(&mut self, item: Self::Item);
+
+    /// # Safety
+    ///
+    /// `new_len` must be <= the buffer's capacity, and every element up to `new_len` must
+    /// already be initialized.
+    unsafe fn fc_set_len(&mut self, new_len: usize);
+}
+
+impl<T> FixedCapacityBuffer for Vec<T> {
+    type Item = T;
+    type Error = TryReserveError;
+
+    #[inline]
+    fn fc_len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn fc_capacity(&self) -> usize {
+        self.capacity()
+    }
+
+    #[inline]
+    fn fc_as_slice(&self) -> &[T] {
+        self.as_slice()
+    }
+
+    #[inline]
+    fn fc_as_mut_slice(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+
+    #[inline]
+    fn fc_as_mut_ptr(&mut self) -> *mut T {
+        self.as_mut_ptr()
+    }
+
+    #[inline]
+    fn fc_reserve(&mut self, additional: usize) {
+        self.reserve(additional)
+    }
+
+    #[inline]
+    fn fc_try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.try_reserve(additional)
+    }
+
+    #[inline]
+    fn fc_push(&mut self, item: T) {
+        self.push(item)
+    }
+
+    #[inline]
+    unsafe fn fc_set_len(&mut self, new_len: usize) {
+        self.set_len(new_len)
+    }
+}
 
+#[cfg(feature = "allocator-api2")]
+impl<T, A: Allocator> FixedCapacityBuffer for AllocVec<T, A> {
+    type Item = T;
+    type Error = allocator_api2::collections::TryReserveError;
+
+    #[inline]
+    fn fc_len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn fc_capacity(&self) -> usize {
+        self.capacity()
+    }
+
+    #[inline]
+    fn fc_as_slice(&self) -> &[T] {
+        self.as_slice()
+    }
+
+    #[inline]
+    fn fc_as_mut_slice(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+
+    #[inline]
+    fn fc_as_mut_ptr(&mut self) -> *mut T {
+        self.as_mut_ptr()
+    }
+
+    #[inline]
+    fn fc_reserve(&mut self, additional: usize) {
+        self.reserve(additional)
+    }
+
+    #[inline]
+    fn fc_try_reserve(&mut self, additional: usize) -> Result<(), Self::Error> {
+        self.try_reserve(additional)
+    }
+
+    #[inline]
+    fn fc_push(&mut self, item: T) {
+        self.push(item)
+    }
+
+    #[inline]
+    unsafe fn fc_set_len(&mut self, new_len: usize) {
+        self.set_len(new_len)
+    }
+}
+
+/// A safe wrapper around a buffer which is not allowed to reallocate.
+///
+/// Generic over the backing buffer `B` (any [`FixedCapacityBuffer`] with `Item = T`),
+/// defaulting to a plain `Vec<T>`.
+#[derive(Debug)]
+pub struct FixedCapacityVec<'a, T, B: FixedCapacityBuffer<Item = T> = Vec<T>>
+where
+    T: 'a,
+    B: 'a,
+{
+    start: usize,
+    max_len: usize,
+    buffer: &'a mut B,
+    _marker: PhantomData<T>,
+}
+
+/// The read view and write view produced by splitting a buffer.
+pub type FixedCapacitySplit<'a, T, B> = (&'a mut [T], FixedCapacityVec<'a, T, B>);
+
+/// The result of fallibly splitting a buffer, see [`AsFixedCapacityVec::try_with_fixed_capacity`].
+pub type TryFixedCapacitySplit<'a, T, B> =
+    Result<FixedCapacitySplit<'a, T, B>, <B as FixedCapacityBuffer>::Error>;
+
+/// Allows pushing to a Vec while keeping a reference to it's content.
+pub trait AsFixedCapacityVec: FixedCapacityBuffer {
     /// Split a vec to create an initialized "read" view and an extendable "write" view
     ///
     /// Allow extending a Vec while keeping a reference to the previous content. The "read" view
@@ -64,37 +226,56 @@ pub trait AsFixedCapacityVec {
     /// }
     /// assert_eq!(vec, &[1, 2, 1, 2, 1, 2]);
     /// ```
-    fn with_fixed_capacity(
+    fn with_fixed_capacity(&mut self, capacity: usize) -> FixedCapacitySplit<'_, Self::Item, Self>
+    where
+        Self: Sized;
+
+    /// Fallible version of [`with_fixed_capacity`](Self::with_fixed_capacity).
+    ///
+    /// Instead of aborting the process on allocation failure, this reports it as an error.
+    /// Since a `FixedCapacityVec` never reallocates after construction, this is the only place
+    /// fallibility can occur: once this call succeeds, `push`/`extend_from_slice` on the returned
+    /// value never allocate. Pushing more than `capacity` elements in total still panics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer still has zero capacity after reserving (e.g. an empty buffer
+    /// split with a `capacity` of 0).
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    /// ```
+    /// use fixed_capacity_vec::AsFixedCapacityVec;
+    /// let mut vec = Vec::new();
+    /// vec.push(1);
+    /// vec.push(2);
+    /// {
+    ///     let (old_data, mut extent) = vec.try_with_fixed_capacity(4).unwrap();
+    ///     extent.extend_from_slice(old_data);
+    /// }
+    /// assert_eq!(vec, &[1, 2, 1, 2]);
+    /// ```
+    fn try_with_fixed_capacity(
         &mut self,
         capacity: usize,
-    ) -> (&mut [Self::Item], FixedCapacityVec<Self::Item>);
-}
-
-/// A safe wrapper around a Vec which is not allowed to reallocate
-#[derive(Debug)]
-pub struct FixedCapacityVec<'a, T>
-where
-    T: 'a,
-{
-    start: usize,
-    max_len: usize,
-    buffer: &'a mut Vec<T>,
+    ) -> TryFixedCapacitySplit<'_, Self::Item, Self>
+    where
+        Self: Sized;
 }
 
-impl<T> AsFixedCapacityVec for Vec<T> {
-    type Item = T;
-
-    fn with_fixed_capacity(&mut self, capacity: usize) -> (&mut [T], FixedCapacityVec<T>) {
-        let len = self.len();
+impl<B: FixedCapacityBuffer> AsFixedCapacityVec for B {
+    fn with_fixed_capacity(&mut self, capacity: usize) -> FixedCapacitySplit<'_, Self::Item, B> {
+        let len = self.fc_len();
         // Ensure the vector can fit `capacity` more elements after its current len() without reallocating
-        self.reserve(capacity);
-        debug_assert!(self.capacity() - len >= capacity);
+        self.fc_reserve(capacity);
+        debug_assert!(self.fc_capacity() - len >= capacity);
 
         // Vec's internal pointer should always point to a non-null pointer. This is important for
         // slice's from_raw_parts method.
         // TODO: Check if this assert is needed
-        assert!(self.capacity() > 0);
-        let raw_ptr = self.as_mut_ptr();
+        assert!(self.fc_capacity() > 0);
+        let raw_ptr = self.fc_as_mut_ptr();
         let init_slice = unsafe { slice::from_raw_parts_mut(raw_ptr, len) };
 
         (
@@ -103,14 +284,44 @@ impl<T> AsFixedCapacityVec for Vec<T> {
                 start: len,
                 max_len: len + capacity,
                 buffer: self,
+                _marker: PhantomData,
             },
         )
     }
+
+    fn try_with_fixed_capacity(
+        &mut self,
+        capacity: usize,
+    ) -> TryFixedCapacitySplit<'_, Self::Item, B> {
+        let len = self.fc_len();
+        // Ensure the vector can fit `capacity` more elements after its current len() without
+        // reallocating, returning an error instead of aborting if that's not possible.
+        self.fc_try_reserve(capacity)?;
+        debug_assert!(self.fc_capacity() - len >= capacity);
+
+        // Vec's internal pointer should always point to a non-null pointer. This is important for
+        // slice's from_raw_parts method.
+        // TODO: Check if this assert is needed
+        assert!(self.fc_capacity() > 0);
+        let raw_ptr = self.fc_as_mut_ptr();
+        let init_slice = unsafe { slice::from_raw_parts_mut(raw_ptr, len) };
+
+        Ok((
+            init_slice,
+            FixedCapacityVec {
+                start: len,
+                max_len: len + capacity,
+                buffer: self,
+                _marker: PhantomData,
+            },
+        ))
+    }
 }
 
-impl<'a, T> FixedCapacityVec<'a, T>
+impl<'a, T, B> FixedCapacityVec<'a, T, B>
 where
     T: 'a + Copy,
+    B: 'a + FixedCapacityBuffer<Item = T>,
 {
     /// Appends all elements in a slice to the buffer.
     ///
@@ -134,9 +345,10 @@ where
     pub fn extend_from_slice(&mut self, other: &[T]) {
         assert!(other.len() <= self.additional_cap());
         unsafe {
-            let len = self.buffer.len();
-            self.buffer.set_len(len + other.len());
-            self.buffer.get_unchecked_mut(len..).copy_from_slice(other);
+            let len = self.buffer.fc_len();
+            let dst = self.buffer.fc_as_mut_ptr().add(len);
+            std::ptr::copy_nonoverlapping(other.as_ptr(), dst, other.len());
+            self.buffer.fc_set_len(len + other.len());
         }
     }
 
@@ -170,8 +382,8 @@ where
         // and `rem` is the remaining part of `n`.
 
         // `2^expn` repetition is done by doubling `buf` `expn`-times.
-        let start_pos = self.buffer.len();
-        let buf_start = unsafe { (self.buffer.as_mut_ptr() as *mut T).add(start_pos) };
+        let start_pos = self.buffer.fc_len();
+        let buf_start = unsafe { self.buffer.fc_as_mut_ptr().add(start_pos) };
         let mut buf_fill = buf_start;
         let mut copy_size = slice.len();
 
@@ -206,18 +418,84 @@ where
             }
         }
         unsafe {
-            self.buffer.set_len(start_pos + cap_needed);
+            self.buffer.fc_set_len(start_pos + cap_needed);
+        }
+    }
+
+    /// Repeats the fragment `src` of the already written data (read view + write view) until
+    /// `total_len` elements have been appended.
+    ///
+    /// # Panics
+    ///
+    /// If `src.start` is greater than `src.end`.
+    ///
+    /// If `src.end` is greater than the number of elements written so far (read view + write
+    /// view).
+    ///
+    /// If `total_len` exceeds the remaining capacity.
+    ///
+    /// If `total_len > 0` and `src` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_capacity_vec::AsFixedCapacityVec;
+    /// let mut vec = vec![1, 2, 3];
+    /// {
+    ///     let (_, mut extend) = vec.with_fixed_capacity(5);
+    ///     extend.extend_from_within(1..3, 5);
+    /// }
+    /// assert_eq!(&vec[..], &[1, 2, 3, 2, 3, 2, 3, 2]);
+    /// ```
+    #[inline]
+    pub fn extend_from_within(&mut self, src: Range<usize>, total_len: usize) {
+        assert!(src.start <= src.end);
+        assert!(src.end <= self.buffer.fc_len());
+        assert!(total_len <= self.additional_cap());
+        if total_len == 0 {
+            return;
+        }
+        let frag_len = src.end - src.start;
+        assert!(frag_len > 0);
+
+        let start_pos = self.buffer.fc_len();
+        let buf_ptr = self.buffer.fc_as_mut_ptr();
+        let frag_start = unsafe { buf_ptr.add(src.start) };
+        let buf_start = unsafe { buf_ptr.add(start_pos) };
+
+        // Initial copy of the fragment itself, all other copies source from the already
+        // copied data.
+        let mut copy_size = frag_len.min(total_len);
+        unsafe {
+            std::ptr::copy_nonoverlapping(frag_start, buf_start, copy_size);
+        }
+
+        // Double the already-copied block until it covers `total_len`, same trick as
+        // `extend_with_repeat`. Each copy sources from data written strictly before it, so
+        // it is always non-overlapping with its destination.
+        while copy_size < total_len {
+            let to_copy = copy_size.min(total_len - copy_size);
+            unsafe {
+                std::ptr::copy_nonoverlapping(buf_start, buf_start.add(copy_size), to_copy);
+            }
+            copy_size += to_copy;
+        }
+
+        unsafe {
+            self.buffer.fc_set_len(start_pos + total_len);
         }
     }
 }
-impl<'a, T> FixedCapacityVec<'a, T>
+
+impl<'a, T, B> FixedCapacityVec<'a, T, B>
 where
     T: 'a,
+    B: 'a + FixedCapacityBuffer<Item = T>,
 {
     /// Returns the number of "empty" slots in this FixedCapacityVec
     #[inline]
     fn additional_cap(&self) -> usize {
-        self.max_len - self.buffer.len()
+        self.max_len - self.buffer.fc_len()
     }
 
     /// Appends an element to the back of a collection.
@@ -242,7 +520,7 @@ where
     #[inline]
     pub fn push(&mut self, item: T) {
         assert!(self.additional_cap() > 0);
-        self.buffer.push(item)
+        self.buffer.fc_push(item)
     }
 
     #[inline]
@@ -252,61 +530,165 @@ where
 
     #[inline]
     pub fn len(&mut self) -> usize {
-        self.buffer.len() - self.start
+        self.buffer.fc_len() - self.start
+    }
+
+    /// Returns the remaining spare capacity as a slice of `MaybeUninit<T>`.
+    ///
+    /// This allows writing into the reserved but not yet initialized tail directly, without
+    /// going through `push`, which is useful for filling non-`Copy` types or writing via
+    /// `std::io::Read::read`. Use `advance` afterwards to commit the written elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_capacity_vec::AsFixedCapacityVec;
+    /// use std::mem::MaybeUninit;
+    /// let mut vec = vec![1, 2];
+    /// {
+    ///     let (_, mut extend) = vec.with_fixed_capacity(2);
+    ///     let spare = extend.spare_capacity_mut();
+    ///     spare[0] = MaybeUninit::new(3);
+    ///     spare[1] = MaybeUninit::new(4);
+    ///     unsafe {
+    ///         extend.advance(2);
+    ///     }
+    /// }
+    /// assert_eq!(&vec[..], &[1, 2, 3, 4]);
+    /// ```
+    #[inline]
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        let len = self.buffer.fc_len();
+        let additional = self.max_len - len;
+        unsafe {
+            let ptr = self.buffer.fc_as_mut_ptr().add(len) as *mut MaybeUninit<T>;
+            slice::from_raw_parts_mut(ptr, additional)
+        }
+    }
+
+    /// Commits `n` elements of the spare capacity as initialized, extending the buffer by `n`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the first `n` elements of `spare_capacity_mut()` have
+    /// actually been initialized.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is greater than the remaining capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_capacity_vec::AsFixedCapacityVec;
+    /// use std::mem::MaybeUninit;
+    /// let mut vec: Vec<i32> = Vec::new();
+    /// {
+    ///     let (_, mut extend) = vec.with_fixed_capacity(1);
+    ///     extend.spare_capacity_mut()[0] = MaybeUninit::new(42);
+    ///     unsafe {
+    ///         extend.advance(1);
+    ///     }
+    /// }
+    /// assert_eq!(&vec[..], &[42]);
+    /// ```
+    #[inline]
+    pub unsafe fn advance(&mut self, n: usize) {
+        assert!(n <= self.additional_cap());
+        let new_len = self.buffer.fc_len() + n;
+        self.buffer.fc_set_len(new_len);
     }
 }
 
-impl<'a, T> Deref for FixedCapacityVec<'a, T>
+impl<'a, T, B> Deref for FixedCapacityVec<'a, T, B>
 where
     T: 'a,
+    B: 'a + FixedCapacityBuffer<Item = T>,
 {
     type Target = [T];
 
     fn deref(&self) -> &<Self as Deref>::Target {
-        &self.buffer[self.start..self.buffer.len()]
+        &self.buffer.fc_as_slice()[self.start..]
     }
 }
 
-impl<'a, T> DerefMut for FixedCapacityVec<'a, T>
+impl<'a, T, B> DerefMut for FixedCapacityVec<'a, T, B>
 where
     T: 'a,
+    B: 'a + FixedCapacityBuffer<Item = T>,
 {
     fn deref_mut(&mut self) -> &mut <Self as Deref>::Target {
         let start = self.start;
-        &mut self.buffer[start..]
+        &mut self.buffer.fc_as_mut_slice()[start..]
     }
 }
 
-impl<'a, T> Extend<T> for FixedCapacityVec<'a, T>
+impl<'a, T, B> Extend<T> for FixedCapacityVec<'a, T, B>
 where
     T: 'a + Clone,
+    B: 'a + FixedCapacityBuffer<Item = T>,
 {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         for item in iter {
             assert!(self.additional_cap() > 0);
-            self.buffer.push(item)
+            self.buffer.fc_push(item)
         }
     }
 }
 
-impl<'a, T> AsRef<[T]> for FixedCapacityVec<'a, T>
+impl<'a, T, B> AsRef<[T]> for FixedCapacityVec<'a, T, B>
 where
     T: 'a,
+    B: 'a + FixedCapacityBuffer<Item = T>,
 {
     fn as_ref(&self) -> &[T] {
         &self[..]
     }
 }
 
-impl<'a, T> AsMut<[T]> for FixedCapacityVec<'a, T>
+impl<'a, T, B> AsMut<[T]> for FixedCapacityVec<'a, T, B>
 where
     T: 'a,
+    B: 'a + FixedCapacityBuffer<Item = T>,
 {
     fn as_mut(&mut self) -> &mut [T] {
         &mut self[..]
     }
 }
 
+/// Writes as many bytes as fit without reallocating.
+///
+/// Once capacity is exhausted, `write` returns `Ok(0)`, which makes `write_all` fail with
+/// `ErrorKind::WriteZero` via its default implementation.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_capacity_vec::AsFixedCapacityVec;
+/// use std::io::Write;
+/// let mut vec = Vec::new();
+/// {
+///     let (_, mut extend) = vec.with_fixed_capacity(4);
+///     let written = extend.write(&[1, 2, 3, 4, 5]).unwrap();
+///     assert_eq!(written, 4);
+/// }
+/// assert_eq!(&vec[..], &[1, 2, 3, 4]);
+/// ```
+impl<'a, B> io::Write for FixedCapacityVec<'a, u8, B>
+where
+    B: 'a + FixedCapacityBuffer<Item = u8>,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.additional_cap());
+        self.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -402,4 +784,136 @@ mod tests {
         let (_, mut extend) = vec.with_fixed_capacity(1);
         extend.extend_with_repeat(&[1], 5);
     }
+
+    #[test]
+    fn test_try_with_fixed_capacity_ok() {
+        let mut vec = vec![1, 2, 3, 4];
+        {
+            let (content, mut extend_end) = vec.try_with_fixed_capacity(1).unwrap();
+            assert_eq!(content, &[1, 2, 3, 4]);
+            extend_end.push(5);
+        }
+        assert_eq!(vec, &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_try_with_fixed_capacity_err() {
+        let mut vec: Vec<i32> = Vec::new();
+        assert!(vec.try_with_fixed_capacity(usize::max_value()).is_err());
+    }
+
+    #[test]
+    fn test_spare_capacity_mut_and_advance() {
+        let mut vec = vec![1, 2];
+        {
+            let (_, mut extend) = vec.with_fixed_capacity(3);
+            {
+                let spare = extend.spare_capacity_mut();
+                assert_eq!(spare.len(), 3);
+                for (i, slot) in spare.iter_mut().enumerate() {
+                    *slot = MaybeUninit::new(i as i32);
+                }
+            }
+            unsafe {
+                extend.advance(3);
+            }
+        }
+        assert_eq!(&vec[..], &[1, 2, 0, 1, 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_advance_over_capacity() {
+        let mut vec: Vec<i32> = Vec::new();
+        let (_, mut extend) = vec.with_fixed_capacity(2);
+        unsafe {
+            extend.advance(3);
+        }
+    }
+
+    #[test]
+    fn test_extend_from_within() {
+        let mut vec = vec![1, 2, 3];
+        {
+            let (_, mut extend) = vec.with_fixed_capacity(5);
+            extend.extend_from_within(1..3, 5);
+        }
+        assert_eq!(&vec[..], &[1, 2, 3, 2, 3, 2, 3, 2]);
+    }
+
+    #[test]
+    fn test_extend_from_within_sources_freshly_written_data() {
+        let mut vec: Vec<i32> = Vec::new();
+        {
+            let (_, mut extend) = vec.with_fixed_capacity(6);
+            extend.push(1);
+            extend.extend_from_within(0..1, 5);
+        }
+        assert_eq!(&vec[..], &[1, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_extend_from_within_src_out_of_range() {
+        let mut vec = vec![1, 2];
+        let (_, mut extend) = vec.with_fixed_capacity(2);
+        extend.extend_from_within(0..3, 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_extend_from_within_over_capacity() {
+        let mut vec = vec![1, 2];
+        let (_, mut extend) = vec.with_fixed_capacity(2);
+        extend.extend_from_within(0..2, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_extend_from_within_backwards_range() {
+        let mut vec = vec![1, 2, 3];
+        let (_, mut extend) = vec.with_fixed_capacity(3);
+        let start = 1000;
+        let end = 1;
+        extend.extend_from_within(start..end, 3);
+    }
+
+    #[test]
+    fn test_write() {
+        use std::io::Write;
+        let mut vec = Vec::new();
+        {
+            let (_, mut extend) = vec.with_fixed_capacity(4);
+            let written = extend.write(&[1, 2, 3, 4, 5]).unwrap();
+            assert_eq!(written, 4);
+        }
+        assert_eq!(&vec[..], &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_write_all_fails_when_exhausted() {
+        use std::io::Write;
+        let mut vec = Vec::new();
+        let (_, mut extend) = vec.with_fixed_capacity(2);
+        assert!(extend.write_all(&[1, 2, 3]).is_err());
+    }
+}
+
+#[cfg(all(test, feature = "allocator-api2"))]
+mod allocator_api2_tests {
+    use super::*;
+    use allocator_api2::alloc::Global;
+
+    #[test]
+    fn test_with_fixed_capacity_global_allocator() {
+        let mut vec: AllocVec<i32, Global> = AllocVec::new_in(Global);
+        vec.push(1);
+        vec.push(2);
+        {
+            let (content, mut extend) = vec.with_fixed_capacity(2);
+            assert_eq!(content, &[1, 2]);
+            extend.extend_from_slice(content);
+        }
+        assert_eq!(&vec[..], &[1, 2, 1, 2]);
+    }
 }